@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use log::info;
+
+use serde::{Deserialize, Serialize};
+
+use super::offsets::OffsetMap;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Cache {
+    pub build_number: u32,
+    pub offsets: OffsetMap,
+}
+
+impl Cache {
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(path: &Path, build_number: u32, offsets: &OffsetMap) -> Result<()> {
+        let cache = Cache {
+            build_number,
+            offsets: offsets.clone(),
+        };
+
+        let content = serde_json::to_string_pretty(&cache)?;
+
+        fs::write(path, content)
+            .with_context(|| format!("failed to write offset cache: {}", path.display()))?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct OffsetDelta {
+    pub old: u32,
+    pub new: u32,
+    pub delta: i64,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct OffsetDiff {
+    pub added: OffsetMap,
+    pub removed: OffsetMap,
+    pub changed: BTreeMap<String, BTreeMap<String, OffsetDelta>>,
+}
+
+impl OffsetDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+pub fn diff(old: &OffsetMap, new: &OffsetMap) -> OffsetDiff {
+    let mut diff = OffsetDiff::default();
+
+    for (module, new_offsets) in new {
+        let old_offsets = old.get(module);
+
+        for (name, &new_rva) in new_offsets {
+            match old_offsets.and_then(|offsets| offsets.get(name)) {
+                None => {
+                    diff.added
+                        .entry(module.clone())
+                        .or_default()
+                        .insert(name.clone(), new_rva);
+                }
+                Some(&old_rva) if old_rva != new_rva => {
+                    diff.changed.entry(module.clone()).or_default().insert(
+                        name.clone(),
+                        OffsetDelta {
+                            old: old_rva,
+                            new: new_rva,
+                            delta: new_rva as i64 - old_rva as i64,
+                        },
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    for (module, old_offsets) in old {
+        let new_offsets = new.get(module);
+
+        for (name, &old_rva) in old_offsets {
+            if new_offsets.and_then(|offsets| offsets.get(name)).is_none() {
+                diff.removed
+                    .entry(module.clone())
+                    .or_default()
+                    .insert(name.clone(), old_rva);
+            }
+        }
+    }
+
+    diff
+}
+
+pub fn log_diff(diff: &OffsetDiff) {
+    if diff.is_empty() {
+        info!("no offsets changed since the last cached scan");
+
+        return;
+    }
+
+    for (module, offsets) in &diff.added {
+        for (name, rva) in offsets {
+            info!("+ {module}::{name} = {:#X}", rva);
+        }
+    }
+
+    for (module, offsets) in &diff.removed {
+        for (name, rva) in offsets {
+            info!("- {module}::{name} (was {:#X})", rva);
+        }
+    }
+
+    for (module, offsets) in &diff.changed {
+        for (name, delta) in offsets {
+            info!(
+                "~ {module}::{name} = {:#X} -> {:#X} ({:+#X})",
+                delta.old, delta.new, delta.delta
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offsets(entries: &[(&str, &str, u32)]) -> OffsetMap {
+        let mut map = OffsetMap::new();
+        for &(module, name, rva) in entries {
+            map.entry(module.to_string())
+                .or_default()
+                .insert(name.to_string(), rva);
+        }
+        map
+    }
+
+    #[test]
+    fn diff_classifies_added_removed_and_changed_offsets() {
+        let old = offsets(&[
+            ("client.dll", "dwGlobalVars", 0x100),
+            ("client.dll", "dwPlantedC4", 0x200),
+            ("engine2.dll", "dwBuildNumber", 0x300),
+        ]);
+        let new = offsets(&[
+            ("client.dll", "dwGlobalVars", 0x150),
+            ("engine2.dll", "dwBuildNumber", 0x300),
+            ("engine2.dll", "dwWindowWidth", 0x400),
+        ]);
+        let diff = diff(&old, &new);
+        assert_eq!(diff.added["engine2.dll"]["dwWindowWidth"], 0x400);
+        assert_eq!(diff.removed["client.dll"]["dwPlantedC4"], 0x200);
+        let delta = &diff.changed["client.dll"]["dwGlobalVars"];
+        assert_eq!((delta.old, delta.new, delta.delta), (0x100, 0x150, 0x50));
+        assert!(!diff.changed["client.dll"].contains_key("dwPlantedC4"));
+        assert!(!diff.added.contains_key("client.dll"));
+    }
+
+    #[test]
+    fn diff_of_identical_maps_is_empty() {
+        let offsets = offsets(&[("client.dll", "dwGlobalVars", 0x100)]);
+        assert!(diff(&offsets, &offsets).is_empty());
+    }
+}
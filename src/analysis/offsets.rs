@@ -1,8 +1,10 @@
 use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 
-use log::{debug, error};
+use log::{debug, error, warn};
 
 use memflow::prelude::v1::*;
 
@@ -14,37 +16,150 @@ use phf::{Map, phf_map};
 
 pub type OffsetMap = BTreeMap<String, BTreeMap<String, Rva>>;
 
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    pub ambiguous: BTreeMap<String, Vec<Rva>>,
+    pub missing: Vec<String>,
+}
+
+pub type PatternOverrides = BTreeMap<String, BTreeMap<String, Vec<Atom>>>;
+
+pub fn load_pattern_overrides(path: &Path) -> Result<PatternOverrides> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read signature file: {}", path.display()))?;
+
+    let raw: BTreeMap<String, BTreeMap<String, String>> =
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&content)
+                .with_context(|| format!("failed to parse signature file: {}", path.display()))?,
+            _ => toml::from_str(&content)
+                .with_context(|| format!("failed to parse signature file: {}", path.display()))?,
+        };
+
+    let mut overrides = PatternOverrides::new();
+
+    for (module, patterns) in raw {
+        let mut parsed = BTreeMap::new();
+
+        for (name, pattern) in patterns {
+            let atoms = pelite::pattern::parse(&pattern)
+                .with_context(|| format!("invalid signature pattern for `{module}::{name}`: {pattern}"))?;
+
+            if save_len(&atoms) <= 1 {
+                bail!(
+                    "signature pattern for `{module}::{name}` has no capture group: {pattern}"
+                );
+            }
+
+            parsed.insert(name, atoms);
+        }
+
+        overrides.insert(module, parsed);
+    }
+
+    Ok(overrides)
+}
+
+type Callback = fn(&PeView, &mut BTreeMap<String, Rva>, Rva);
+type Candidate = (Option<u32>, Option<u32>, &'static [Atom], Option<Callback>);
+
+const fn candidate(
+    min: Option<u32>,
+    max: Option<u32>,
+    pattern: &'static [Atom],
+    callback: Option<Callback>,
+) -> Candidate {
+    (min, max, pattern, callback)
+}
+
+fn scan_unique(view: &PeView<'_>, name: &str, pat: &[Atom], report: &mut ScanReport) -> Option<Rva> {
+    let mut matches = view.scanner().matches_code(pat);
+    let mut save = vec![0; save_len(pat)];
+    let mut candidates = Vec::new();
+
+    while matches.next(&mut save) {
+        candidates.push(save[1] as Rva);
+    }
+
+    let Some(&rva) = candidates.first() else {
+        error!("outdated pattern: {}", name);
+        report.missing.push(name.to_string());
+
+        return None;
+    };
+
+    if candidates.len() > 1 {
+        for addr in &candidates {
+            warn!("ambiguous pattern {}: candidate at rva {:#X}", name, addr);
+        }
+
+        report.ambiguous.insert(name.to_string(), candidates);
+    }
+
+    Some(rva)
+}
+
+fn select_candidate(candidates: &'static [Candidate], build: Option<u32>) -> Option<&'static Candidate> {
+    if let Some(build) = build {
+        let matched = candidates.iter().find(|(min, max, ..)| {
+            min.is_none_or(|min| build >= min) && max.is_none_or(|max| build <= max)
+        });
+
+        if matched.is_some() {
+            return matched;
+        }
+    }
+
+    candidates
+        .iter()
+        .rev()
+        .find(|(_, max, ..)| max.is_none())
+        .or_else(|| candidates.last())
+}
+
 macro_rules! pattern_map {
     ($($module:ident => {
-        $($name:expr => $pattern:expr $(=> $callback:expr)?),+ $(,)?
+        $($name:expr => [$($min:expr, $max:expr => $pattern:expr $(=> $callback:expr)?);+ $(;)?]),+ $(,)?
     }),+ $(,)?) => {
         $(
             mod $module {
                 use super::*;
 
-                pub(super) const PATTERNS: Map<
-                    &'static str,
-                    (
-                        &'static [Atom],
-                        Option<fn(&PeView, &mut BTreeMap<String, Rva>, Rva)>,
-                    ),
-                > = phf_map! {
-                    $($name => ($pattern, $($callback)?)),+
+                pub(super) const PATTERNS: Map<&'static str, &'static [Candidate]> = phf_map! {
+                    $($name => &[$(candidate($min, $max, $pattern, $($callback)?)),+]),+
                 };
 
-                pub fn offsets(view: PeView<'_>) -> BTreeMap<String, Rva> {
+                pub fn offsets(
+                    view: PeView<'_>,
+                    build: Option<u32>,
+                    overrides: Option<&BTreeMap<String, Vec<Atom>>>,
+                    report: &mut ScanReport,
+                ) -> BTreeMap<String, Rva> {
                     let mut map = BTreeMap::new();
+                    let mut overridden = std::collections::BTreeSet::new();
 
-                    for (&name, (pat, callback)) in &PATTERNS {
-                        let mut save = vec![0; save_len(pat)];
+                    for (&name, candidates) in &PATTERNS {
+                        let user_pattern = overrides.and_then(|o| o.get(name));
 
-                        if !view.scanner().finds_code(pat, &mut save) {
-                            error!("outdated pattern: {}", name);
+                        let (pat, callback): (&[Atom], _) = if let Some(pat) = user_pattern {
+                            overridden.insert(name);
 
-                            continue;
-                        }
+                            (pat.as_slice(), None)
+                        } else {
+                            match select_candidate(candidates, build) {
+                                Some((_, _, pat, callback)) => (pat, *callback),
+                                None => {
+                                    error!("outdated pattern: {}", name);
+                                    report.missing.push(name.to_string());
+
+                                    continue;
+                                }
+                            }
+                        };
 
-                        let rva = save[1];
+                        let Some(rva) = scan_unique(&view, name, pat, report) else {
+                            continue;
+                        };
 
                         map.insert(name.to_string(), rva);
 
@@ -53,6 +168,18 @@ macro_rules! pattern_map {
                         }
                     }
 
+                    for (name, pat) in overrides.into_iter().flatten() {
+                        if overridden.contains(name.as_str()) {
+                            continue;
+                        }
+
+                        let Some(rva) = scan_unique(&view, name, pat, report) else {
+                            continue;
+                        };
+
+                        map.insert(name.clone(), rva);
+                    }
+
                     for (name, value) in &map {
                         debug!(
                             "found offset: {} at {:#X} ({}.dll + {:#X})",
@@ -72,63 +199,191 @@ macro_rules! pattern_map {
 
 pattern_map! {
     client => {
-        "dwCSGOInput" => pattern!("488905${'} 0f57c0 0f1105") => Some(|view, map, rva| {
+        "dwCSGOInput" => [None, None => pattern!("488905${'} 0f57c0 0f1105") => Some(|view, map, rva| {
             let mut save = [0; 2];
 
             if view.scanner().finds_code(pattern!("f2420f108428u4"), &mut save) {
                 map.insert("dwViewAngles".to_string(), rva + save[1]);
             }
-        }),
-        "dwEntityList" => pattern!("488935${'} 4885f6") => None,
-        "dwGameEntitySystem" => pattern!("488b3d${'} 48893d") => None,
-        "dwGameEntitySystem_highestEntityIndex" => pattern!("ff81u4 4885d2") => None,
-        "dwGameRules" => pattern!("48891d${'} ff15${} 84c0") => None,
-        "dwGlobalVars" => pattern!("488915${'} 488942") => None,
-        "dwGlowManager" => pattern!("488b05${'} c3 cccccccccccccccc 8b41") => None,
-        "dwLocalPlayerController" => pattern!("488b05${'} 4189be") => None,
-        "dwPlantedC4" => pattern!("488b15${'} 41ffc0") => None,
-        "dwPrediction" => pattern!("488d05${'} c3 cccccccccccccccc 405356 4154") => Some(|view, map, rva| {
+        })],
+        "dwEntityList" => [None, None => pattern!("488935${'} 4885f6") => None],
+        "dwGameEntitySystem" => [None, None => pattern!("488b3d${'} 48893d") => None],
+        "dwGameEntitySystem_highestEntityIndex" => [None, None => pattern!("ff81u4 4885d2") => None],
+        "dwGameRules" => [None, None => pattern!("48891d${'} ff15${} 84c0") => None],
+        "dwGlobalVars" => [None, None => pattern!("488915${'} 488942") => None],
+        "dwGlowManager" => [None, None => pattern!("488b05${'} c3 cccccccccccccccc 8b41") => None],
+        "dwLocalPlayerController" => [None, None => pattern!("488b05${'} 4189be") => None],
+        "dwPlantedC4" => [None, None => pattern!("488b15${'} 41ffc0") => None],
+        "dwPrediction" => [None, None => pattern!("488d05${'} c3 cccccccccccccccc 405356 4154") => Some(|view, map, rva| {
             let mut save = [0; 2];
 
             if view.scanner().finds_code(pattern!("4c39b6u4 74? 4488be"), &mut save) {
                 map.insert("dwLocalPlayerPawn".to_string(), rva + save[1]);
             }
-        }),
-        "dwSensitivity" => pattern!("488d0d${[8]'} 660f6ecd") => None,
-        "dwSensitivity_sensitivity" => pattern!("488d7eu1 480fbae0? 72? 85d2 490f4fff") => None,
-        "dwViewMatrix" => pattern!("488d0d${'} 48c1e006") => None,
-        "dwViewRender" => pattern!("488905${'} 488bc8 4885c0") => None,
-        "dwWeaponC4" => pattern!("488905${'} f7c1[4] 74? 81e1[4] 890d${} 8b05${} 891d${} eb? 488b15${} 488b5c24? ffc0 8905${} 488bc6 488934ea 80be") => None,
+        })],
+        "dwSensitivity" => [None, None => pattern!("488d0d${[8]'} 660f6ecd") => None],
+        "dwSensitivity_sensitivity" => [None, None => pattern!("488d7eu1 480fbae0? 72? 85d2 490f4fff") => None],
+        "dwViewMatrix" => [None, None => pattern!("488d0d${'} 48c1e006") => None],
+        "dwViewRender" => [None, None => pattern!("488905${'} 488bc8 4885c0") => None],
+        "dwWeaponC4" => [None, None => pattern!("488905${'} f7c1[4] 74? 81e1[4] 890d${} 8b05${} 891d${} eb? 488b15${} 488b5c24? ffc0 8905${} 488bc6 488934ea 80be") => None],
     },
     engine2 => {
-        "dwBuildNumber" => pattern!("8905${'} 488d0d${} ff15${} 488b0d") => None,
-        "dwNetworkGameClient" => pattern!("48893d${'} 488d15") => None,
-        "dwNetworkGameClient_clientTickCount" => pattern!("8b81u4 c3 cccccccccccccccccc 8b81${} c3 cccccccccccccccccc 83b9") => None,
-        "dwNetworkGameClient_deltaTick" => pattern!("4c8db7u4 4c897c24") => None,
-        "dwNetworkGameClient_isBackgroundMap" => pattern!("0fb681u4 c3 cccccccccccccccc 0fb681${} c3 cccccccccccccccc 4053") => None,
-        "dwNetworkGameClient_localPlayer" => pattern!("428b94d3u4 5b 49ffe3 32c0 5b c3 cccccccccccccccc 4053") => None,
-        "dwNetworkGameClient_maxClients" => pattern!("8b81u4 c3cccccccccccccccccc 8b81${} ffc0") => None,
-        "dwNetworkGameClient_serverTickCount" => pattern!("8b81u4 c3 cccccccccccccccccc 83b9") => None,
-        "dwNetworkGameClient_signOnState" => pattern!("448b81u4 488d0d") => None,
-        "dwWindowHeight" => pattern!("8b05${'} 8903") => None,
-        "dwWindowWidth" => pattern!("8b05${'} 8907") => None,
+        "dwBuildNumber" => [None, None => pattern!("8905${'} 488d0d${} ff15${} 488b0d") => None],
+        "dwNetworkGameClient" => [None, None => pattern!("48893d${'} 488d15") => None],
+        "dwNetworkGameClient_clientTickCount" => [None, None => pattern!("8b81u4 c3 cccccccccccccccccc 8b81${} c3 cccccccccccccccccc 83b9") => None],
+        "dwNetworkGameClient_deltaTick" => [None, None => pattern!("4c8db7u4 4c897c24") => None],
+        "dwNetworkGameClient_isBackgroundMap" => [None, None => pattern!("0fb681u4 c3 cccccccccccccccc 0fb681${} c3 cccccccccccccccc 4053") => None],
+        "dwNetworkGameClient_localPlayer" => [None, None => pattern!("428b94d3u4 5b 49ffe3 32c0 5b c3 cccccccccccccccc 4053") => None],
+        "dwNetworkGameClient_maxClients" => [None, None => pattern!("8b81u4 c3cccccccccccccccccc 8b81${} ffc0") => None],
+        "dwNetworkGameClient_serverTickCount" => [None, None => pattern!("8b81u4 c3 cccccccccccccccccc 83b9") => None],
+        "dwNetworkGameClient_signOnState" => [None, None => pattern!("448b81u4 488d0d") => None],
+        "dwWindowHeight" => [None, None => pattern!("8b05${'} 8903") => None],
+        "dwWindowWidth" => [None, None => pattern!("8b05${'} 8907") => None],
     },
     input_system => {
-        "dwInputSystem" => pattern!("488905${'} 33c0") => None,
+        "dwInputSystem" => [None, None => pattern!("488905${'} 33c0") => None],
     },
     matchmaking => {
-        "dwGameTypes" => pattern!("488d0d${'} ff90") => None,
+        "dwGameTypes" => [None, None => pattern!("488d0d${'} ff90") => None],
     },
     soundsystem => {
-        "dwSoundSystem" => pattern!("488d05${'} c3 cccccccccccccccc 488915") => None,
-        "dwSoundSystem_engineViewData" => pattern!("0f1147u1 0f104b? 0f118f") => None,
+        "dwSoundSystem" => [None, None => pattern!("488d05${'} c3 cccccccccccccccc 488915") => None],
+        "dwSoundSystem_engineViewData" => [None, None => pattern!("0f1147u1 0f104b? 0f118f") => None],
     },
 }
 
-pub fn offsets<P: Process + MemoryView>(process: &mut P) -> Result<OffsetMap> {
-    let mut map = BTreeMap::new();
+fn resolve_build_number(view: &PeView<'_>, image: &[u8], pattern_override: Option<&[Atom]>) -> Option<u32> {
+    let pat: &[Atom] = if let Some(pat) = pattern_override {
+        pat
+    } else {
+        let candidates = engine2::PATTERNS.get("dwBuildNumber")?;
+        let (_, _, pat, _) = select_candidate(candidates, None)?;
+
+        pat
+    };
+
+    let mut save = vec![0; save_len(pat)];
+
+    if !view.scanner().finds_code(pat, &mut save) {
+        error!("outdated pattern: dwBuildNumber");
+
+        return None;
+    }
+
+    let rva = save[1] as usize;
+    let bytes = image.get(rva..rva + 4)?;
+
+    let build = u32::from_le_bytes(bytes.try_into().ok()?);
+
+    debug!("detected build number: {}", build);
+
+    Some(build)
+}
+
+fn engine2_build_number_override(signature_file: Option<&Path>) -> Result<Option<Vec<Atom>>> {
+    let overrides = signature_file.map(load_pattern_overrides).transpose()?;
+
+    Ok(overrides
+        .as_ref()
+        .and_then(|o| o.get("engine2.dll"))
+        .and_then(|m| m.get("dwBuildNumber"))
+        .cloned())
+}
+
+pub fn live_build_number<P: Process + MemoryView>(
+    process: &mut P,
+    signature_file: Option<&Path>,
+) -> Result<Option<u32>> {
+    let pattern_override = engine2_build_number_override(signature_file)?;
+
+    let engine2_module = process.module_by_name("engine2.dll")?;
+
+    let engine2_buf = process
+        .read_raw(engine2_module.base, engine2_module.size as _)
+        .data_part()?;
+
+    let engine2_view = PeView::from_bytes(&engine2_buf)?;
+
+    Ok(resolve_build_number(
+        &engine2_view,
+        &engine2_buf,
+        pattern_override.as_deref(),
+    ))
+}
+
+pub fn offline_build_number(modules_dir: &Path, signature_file: Option<&Path>) -> Result<Option<u32>> {
+    let pattern_override = engine2_build_number_override(signature_file)?;
+
+    let engine2_image = read_module_image(&modules_dir.join("engine2.dll"))?;
+    let engine2_view = PeView::from_bytes(&engine2_image)?;
+
+    Ok(resolve_build_number(
+        &engine2_view,
+        &engine2_image,
+        pattern_override.as_deref(),
+    ))
+}
+
+fn finalize(
+    map: BTreeMap<String, BTreeMap<String, Rva>>,
+    reports: &BTreeMap<String, ScanReport>,
+    strict: bool,
+) -> Result<OffsetMap> {
+    let problems: Vec<String> = reports
+        .iter()
+        .flat_map(|(module, report)| {
+            let missing = report
+                .missing
+                .iter()
+                .map(move |name| format!("{module}::{name}: no match"));
+
+            let ambiguous = report.ambiguous.iter().map(move |(name, candidates)| {
+                format!("{module}::{name}: {} candidates", candidates.len())
+            });
+
+            missing.chain(ambiguous)
+        })
+        .collect();
+
+    if problems.is_empty() {
+        return Ok(map);
+    }
+
+    if strict {
+        bail!("strict scan found missing/ambiguous offsets:\n{}", problems.join("\n"));
+    }
 
-    let modules: [(&str, fn(PeView) -> BTreeMap<String, u32>); 5] = [
+    warn!("scan found missing/ambiguous offsets:\n{}", problems.join("\n"));
+
+    Ok(map)
+}
+
+/// Scans every module's offsets given `engine2_buf` (already fetched) and `fetch_buf` to fetch the
+/// other four modules' bytes, whether from a live process or on-disk files; shared by
+/// [`offsets_with_overrides`] and [`offsets_offline`], which only differ in how a module's bytes
+/// are obtained.
+fn scan_modules(
+    engine2_buf: &[u8],
+    signature_file: Option<&Path>,
+    strict: bool,
+    mut fetch_buf: impl FnMut(&str) -> Result<Vec<u8>>,
+) -> Result<OffsetMap> {
+    let mut map = BTreeMap::new();
+    let mut reports = BTreeMap::new();
+
+    let overrides = signature_file.map(load_pattern_overrides).transpose()?;
+    let overrides_for = |module_name: &str| overrides.as_ref().and_then(|o| o.get(module_name));
+
+    #[allow(clippy::type_complexity)]
+    let modules: [(
+        &str,
+        fn(
+            PeView,
+            Option<u32>,
+            Option<&BTreeMap<String, Vec<Atom>>>,
+            &mut ScanReport,
+        ) -> BTreeMap<String, u32>,
+    ); 5] = [
         ("client.dll", client::offsets),
         ("engine2.dll", engine2::offsets),
         ("inputsystem.dll", input_system::offsets),
@@ -136,19 +391,105 @@ pub fn offsets<P: Process + MemoryView>(process: &mut P) -> Result<OffsetMap> {
         ("soundsystem.dll", soundsystem::offsets),
     ];
 
+    let engine2_view = PeView::from_bytes(engine2_buf)?;
+
+    let build_number = resolve_build_number(
+        &engine2_view,
+        engine2_buf,
+        overrides_for("engine2.dll")
+            .and_then(|o| o.get("dwBuildNumber"))
+            .map(Vec::as_slice),
+    );
+
     for (module_name, offsets) in &modules {
+        let mut report = ScanReport::default();
+
+        if *module_name == "engine2.dll" {
+            let offsets = offsets(
+                engine2_view,
+                build_number,
+                overrides_for(module_name),
+                &mut report,
+            );
+
+            map.insert(module_name.to_string(), offsets);
+            reports.insert(module_name.to_string(), report);
+
+            continue;
+        }
+
+        let buf = fetch_buf(module_name)?;
+        let view = PeView::from_bytes(&buf)?;
+
+        let offsets = offsets(view, build_number, overrides_for(module_name), &mut report);
+
+        map.insert(module_name.to_string(), offsets);
+        reports.insert(module_name.to_string(), report);
+    }
+
+    finalize(map, &reports, strict)
+}
+
+pub fn offsets_with_overrides<P: Process + MemoryView>(
+    process: &mut P,
+    signature_file: Option<&Path>,
+    strict: bool,
+) -> Result<OffsetMap> {
+    let engine2_module = process.module_by_name("engine2.dll")?;
+
+    let engine2_buf = process
+        .read_raw(engine2_module.base, engine2_module.size as _)
+        .data_part()?;
+
+    scan_modules(&engine2_buf, signature_file, strict, |module_name| {
         let module = process.module_by_name(module_name)?;
 
-        let buf = process
+        Ok(process
             .read_raw(module.base, module.size as _)
-            .data_part()?;
+            .data_part()?)
+    })
+}
 
-        let view = PeView::from_bytes(&buf)?;
+/// Copies each section to its virtual address rather than its file offset, so the result can be
+/// scanned the same way as a live, mapped module.
+fn read_module_image(path: &Path) -> Result<Vec<u8>> {
+    let bytes =
+        fs::read(path).with_context(|| format!("failed to read module: {}", path.display()))?;
+
+    let file = pelite::pe64::PeFile::from_bytes(&bytes)
+        .with_context(|| format!("failed to parse module: {}", path.display()))?;
+
+    let mut image = vec![0u8; file.optional_header().SizeOfImage as usize];
+
+    let headers_len = (file.optional_header().SizeOfHeaders as usize).min(bytes.len());
+    image[..headers_len].copy_from_slice(&bytes[..headers_len]);
+
+    for section in file.section_headers() {
+        let dest_start = section.VirtualAddress as usize;
+        let dest_len = (section.VirtualSize as usize).min(section.SizeOfRawData as usize);
+        let src_start = section.PointerToRawData as usize;
+
+        if src_start + dest_len > bytes.len() || dest_start + dest_len > image.len() {
+            continue;
+        }
 
-        map.insert(module_name.to_string(), offsets(view));
+        image[dest_start..dest_start + dest_len]
+            .copy_from_slice(&bytes[src_start..src_start + dest_len]);
     }
 
-    Ok(map)
+    Ok(image)
+}
+
+pub fn offsets_offline(
+    modules_dir: &Path,
+    signature_file: Option<&Path>,
+    strict: bool,
+) -> Result<OffsetMap> {
+    let engine2_image = read_module_image(&modules_dir.join("engine2.dll"))?;
+
+    scan_modules(&engine2_image, signature_file, strict, |module_name| {
+        read_module_image(&modules_dir.join(module_name))
+    })
 }
 
 #[cfg(test)]
@@ -159,6 +500,204 @@ mod tests {
 
     use super::*;
 
+    static CANDIDATES: &[Candidate] = &[
+        candidate(Some(10_000), Some(19_999), pattern!("c3"), None),
+        candidate(Some(20_000), None, pattern!("c3"), None),
+    ];
+
+    #[test]
+    fn select_candidate_picks_the_range_containing_the_build() {
+        let (min, max, ..) = select_candidate(CANDIDATES, Some(15_000)).unwrap();
+        assert_eq!((*min, *max), (Some(10_000), Some(19_999)));
+    }
+
+    #[test]
+    fn select_candidate_falls_back_to_the_newest_unbounded_range() {
+        let (min, max, ..) = select_candidate(CANDIDATES, Some(5_000)).unwrap();
+        assert_eq!((*min, *max), (Some(20_000), None));
+    }
+
+    #[test]
+    fn select_candidate_without_a_build_number_uses_the_newest_unbounded_range() {
+        let (min, max, ..) = select_candidate(CANDIDATES, None).unwrap();
+        assert_eq!((*min, *max), (Some(20_000), None));
+    }
+
+    pattern_map! {
+        testmod => {
+            "dwTest" => [
+                None, Some(999) => pattern!("c3'") => None;
+                Some(1000), None => pattern!("90'") => None;
+            ],
+        },
+    }
+
+    #[test]
+    fn pattern_map_picks_the_build_gated_candidate() {
+        let old_image = normalized_test_image(&[0xC3]);
+        let old_view = PeView::from_bytes(&old_image).unwrap();
+        let mut report = ScanReport::default();
+        let offsets = testmod::offsets(old_view, Some(500), None, &mut report);
+        assert_eq!(offsets["dwTest"], 0x1001);
+
+        let new_image = normalized_test_image(&[0x90]);
+        let new_view = PeView::from_bytes(&new_image).unwrap();
+        let mut report = ScanReport::default();
+        let offsets = testmod::offsets(new_view, Some(1500), None, &mut report);
+        assert_eq!(offsets["dwTest"], 0x1001);
+    }
+
+    #[test]
+    fn load_pattern_overrides_accepts_patterns_with_a_capture_group() {
+        let path = std::env::temp_dir().join("cs2_dumper_test_overrides_valid.toml");
+        fs::write(&path, "[client]\ndwFoo = \"488905${'} 90\"\n").unwrap();
+        let overrides = load_pattern_overrides(&path).unwrap();
+        assert!(overrides["client"].contains_key("dwFoo"));
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn load_pattern_overrides_rejects_patterns_without_a_capture_group() {
+        let path = std::env::temp_dir().join("cs2_dumper_test_overrides_invalid.toml");
+        fs::write(&path, "[client]\ndwFoo = \"90\"\n").unwrap();
+        assert!(load_pattern_overrides(&path).is_err());
+        fs::remove_file(&path).ok();
+    }
+
+    fn push_u16(buf: &mut Vec<u8>, v: u16) {
+        buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn push_u32(buf: &mut Vec<u8>, v: u32) {
+        buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn push_u64(buf: &mut Vec<u8>, v: u64) {
+        buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    // Builds a minimal, valid PE64 image with a single `.text` section holding `section_data`,
+    // laid out at VA 0x1000, so `read_module_image`'s normalization can be tested end to end.
+    fn build_test_pe_image(section_data: &[u8]) -> Vec<u8> {
+        const SECTION_VA: u32 = 0x1000;
+        const SECTION_FILE_OFFSET: u32 = 0x200;
+        const SIZE_OF_HEADERS: u32 = 0x200;
+        const SIZE_OF_IMAGE: u32 = 0x2000;
+        const FILE_LEN: usize = 0x400;
+
+        let mut image = Vec::with_capacity(FILE_LEN);
+
+        // DOS header
+        push_u16(&mut image, 0x5A4D);
+        image.resize(60, 0);
+        push_u32(&mut image, 64);
+
+        // NT headers
+        push_u32(&mut image, 0x0000_4550);
+        push_u16(&mut image, 0x8664); // Machine: AMD64
+        push_u16(&mut image, 1); // NumberOfSections
+        push_u32(&mut image, 0); // TimeDateStamp
+        push_u32(&mut image, 0); // PointerToSymbolTable
+        push_u32(&mut image, 0); // NumberOfSymbols
+        push_u16(&mut image, 112); // SizeOfOptionalHeader
+        push_u16(&mut image, 0x0002); // Characteristics: EXECUTABLE_IMAGE
+
+        push_u16(&mut image, 0x20b); // Magic: PE32+
+        push_u16(&mut image, 0); // LinkerVersion
+        push_u32(&mut image, 0x1000); // SizeOfCode
+        push_u32(&mut image, 0); // SizeOfInitializedData
+        push_u32(&mut image, 0); // SizeOfUninitializedData
+        push_u32(&mut image, SECTION_VA); // AddressOfEntryPoint
+        push_u32(&mut image, SECTION_VA); // BaseOfCode
+        push_u64(&mut image, 0x1_4000_0000); // ImageBase
+        push_u32(&mut image, 0x1000); // SectionAlignment
+        push_u32(&mut image, 0x200); // FileAlignment
+        push_u32(&mut image, 0); // OperatingSystemVersion
+        push_u32(&mut image, 0); // ImageVersion
+        push_u32(&mut image, 0); // SubsystemVersion
+        push_u32(&mut image, 0); // Win32VersionValue
+        push_u32(&mut image, SIZE_OF_IMAGE); // SizeOfImage
+        push_u32(&mut image, SIZE_OF_HEADERS); // SizeOfHeaders
+        push_u32(&mut image, 0); // CheckSum
+        push_u16(&mut image, 3); // Subsystem: WINDOWS_CUI
+        push_u16(&mut image, 0); // DllCharacteristics
+        push_u64(&mut image, 0x100000); // SizeOfStackReserve
+        push_u64(&mut image, 0x1000); // SizeOfStackCommit
+        push_u64(&mut image, 0x100000); // SizeOfHeapReserve
+        push_u64(&mut image, 0x1000); // SizeOfHeapCommit
+        push_u32(&mut image, 0); // LoaderFlags
+        push_u32(&mut image, 0); // NumberOfRvaAndSizes
+
+        // Section header: `.text`
+        image.extend_from_slice(b".text\0\0\0");
+        push_u32(&mut image, section_data.len() as u32); // VirtualSize
+        push_u32(&mut image, SECTION_VA);
+        push_u32(&mut image, 0x200); // SizeOfRawData
+        push_u32(&mut image, SECTION_FILE_OFFSET);
+        push_u32(&mut image, 0); // PointerToRelocations
+        push_u32(&mut image, 0); // PointerToLinenumbers
+        push_u16(&mut image, 0); // NumberOfRelocations
+        push_u16(&mut image, 0); // NumberOfLinenumbers
+        push_u32(&mut image, 0x6000_0020); // Characteristics: CNT_CODE | MEM_EXECUTE | MEM_READ
+
+        image.resize(SECTION_FILE_OFFSET as usize, 0);
+        image.extend_from_slice(section_data);
+        image.resize(FILE_LEN, 0xCC);
+
+        image
+    }
+
+    #[test]
+    fn read_module_image_copies_sections_to_their_virtual_address() {
+        let section_data = b"TESTSECTIONDATA!";
+        let path = std::env::temp_dir().join("cs2_dumper_test_module.bin");
+        fs::write(&path, build_test_pe_image(section_data)).unwrap();
+        let image = read_module_image(&path).unwrap();
+        fs::remove_file(&path).ok();
+        assert_eq!(image.len(), 0x2000);
+        assert_eq!(&image[0x1000..0x1000 + section_data.len()], section_data);
+        assert_eq!(image[0x1000 + section_data.len()], 0);
+    }
+
+    fn normalized_test_image(code: &[u8]) -> Vec<u8> {
+        let path = std::env::temp_dir().join("cs2_dumper_test_scan_module.bin");
+        fs::write(&path, build_test_pe_image(code)).unwrap();
+        let image = read_module_image(&path).unwrap();
+        fs::remove_file(&path).ok();
+        image
+    }
+
+    #[test]
+    fn resolve_build_number_uses_an_override_pattern_when_given_one() {
+        let mut code = vec![0xC3];
+        code.extend_from_slice(&1_234_567u32.to_le_bytes());
+        let image = normalized_test_image(&code);
+        let view = PeView::from_bytes(&image).unwrap();
+
+        let pattern_override = pelite::pattern::parse("c3'").unwrap();
+        let build = resolve_build_number(&view, &image, Some(&pattern_override));
+        assert_eq!(build, Some(1_234_567));
+    }
+
+    #[test]
+    fn scan_unique_reports_every_match_but_returns_the_first() {
+        let image = normalized_test_image(&[0xC3, 0x90, 0xC3, 0x90, 0xC3]);
+        let view = PeView::from_bytes(&image).unwrap();
+        let mut report = ScanReport::default();
+        let rva = scan_unique(&view, "dwTest", pattern!("c3'"), &mut report).unwrap();
+        assert_eq!(rva, 0x1001);
+        assert_eq!(report.ambiguous["dwTest"], vec![0x1001, 0x1003, 0x1005]);
+    }
+
+    #[test]
+    fn scan_unique_reports_missing_patterns() {
+        let image = normalized_test_image(&[0x90, 0x90]);
+        let view = PeView::from_bytes(&image).unwrap();
+        let mut report = ScanReport::default();
+        assert!(scan_unique(&view, "dwTest", pattern!("c3'"), &mut report).is_none());
+        assert_eq!(report.missing, vec!["dwTest".to_string()]);
+    }
+
     fn setup() -> Result<IntoProcessInstanceArcBox<'static>> {
         let os = memflow_native::create_os(&OsArgs::default(), LibArc::default())?;
 
@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+#[derive(Debug, Parser)]
+#[command(author, version, about)]
+pub struct Cli {
+    #[arg(long, default_value = "native")]
+    pub connector: String,
+
+    #[arg(long, default_value = "cs2.exe")]
+    pub process: String,
+
+    #[arg(long)]
+    pub offline: Option<PathBuf>,
+
+    #[arg(long)]
+    pub signatures: Option<PathBuf>,
+
+    #[arg(long = "set", value_names = ["KEY", "VALUE"], num_args = 2, action = clap::ArgAction::Append)]
+    pub set: Vec<String>,
+
+    #[arg(long)]
+    pub strict: bool,
+
+    #[arg(long, default_value = "output/offsets_cache.json")]
+    pub cache: PathBuf,
+
+    #[arg(long)]
+    pub no_cache: bool,
+}
+
+impl Cli {
+    pub fn connector_args(&self) -> String {
+        self.set
+            .chunks(2)
+            .map(|pair| format!("{}={}", pair[0], pair[1]))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
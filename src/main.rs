@@ -0,0 +1,89 @@
+mod analysis;
+mod cli;
+
+use std::fs;
+
+use anyhow::Result;
+
+use clap::Parser;
+
+use log::info;
+
+use memflow::prelude::v1::*;
+
+use crate::analysis::cache::{self, Cache};
+use crate::analysis::offsets::OffsetMap;
+use crate::cli::Cli;
+
+fn main() -> Result<()> {
+    env_logger::init();
+
+    let cli = Cli::parse();
+
+    let offsets = if let Some(modules_dir) = &cli.offline {
+        let build_number =
+            analysis::offsets::offline_build_number(modules_dir, cli.signatures.as_deref())?;
+
+        with_cache(&cli, build_number, || {
+            analysis::offsets::offsets_offline(modules_dir, cli.signatures.as_deref(), cli.strict)
+        })?
+    } else {
+        let mut inventory = Inventory::scan();
+
+        let os = inventory
+            .builder()
+            .connector(&cli.connector)
+            .args(str::parse(&cli.connector_args())?)
+            .os("win32")
+            .build()?;
+
+        let mut process = os.into_process_by_name(&cli.process)?;
+        let build_number =
+            analysis::offsets::live_build_number(&mut process, cli.signatures.as_deref())?;
+
+        with_cache(&cli, build_number, || {
+            analysis::offsets::offsets_with_overrides(
+                &mut process,
+                cli.signatures.as_deref(),
+                cli.strict,
+            )
+        })?
+    };
+
+    fs::create_dir_all("output")?;
+    fs::write("output/offsets.json", serde_json::to_string_pretty(&offsets)?)?;
+
+    Ok(())
+}
+
+fn with_cache(
+    cli: &Cli,
+    build_number: Option<u32>,
+    scan: impl FnOnce() -> Result<OffsetMap>,
+) -> Result<OffsetMap> {
+    if cli.no_cache {
+        return scan();
+    }
+
+    let cached = Cache::load(&cli.cache);
+
+    if let (Some(build_number), Some(cache)) = (build_number, &cached) {
+        if cache.build_number == build_number {
+            info!("build {build_number} matches the cache, reusing its offsets");
+
+            return Ok(cache.offsets.clone());
+        }
+    }
+
+    let offsets = scan()?;
+
+    if let Some(build_number) = build_number {
+        if let Some(previous) = &cached {
+            cache::log_diff(&cache::diff(&previous.offsets, &offsets));
+        }
+
+        Cache::save(&cli.cache, build_number, &offsets)?;
+    }
+
+    Ok(offsets)
+}